@@ -0,0 +1,62 @@
+use std::sync::Arc;
+
+use warp::http::StatusCode;
+use warp::{Filter, Rejection, Reply};
+
+/// Rejection raised when a request is missing or presents the wrong
+/// `Authorization: Bearer <token>` header.
+#[derive(Debug)]
+pub struct Unauthorized;
+impl warp::reject::Reject for Unauthorized {}
+
+/// Gates a route behind a bearer token when one is configured. `serve.rs`
+/// puts it once in front of the whole protected route group, so
+/// `server_info` and the static files opt out by sitting outside that group.
+pub fn with_auth(
+    expected_token: Option<Arc<String>>,
+) -> impl Filter<Extract = (), Error = Rejection> + Clone {
+    warp::header::optional::<String>("authorization")
+        .and_then(move |header: Option<String>| {
+            let expected_token = expected_token.clone();
+            async move {
+                let Some(expected_token) = expected_token else {
+                    return Ok(());
+                };
+                let provided = header
+                    .as_deref()
+                    .and_then(|h| h.strip_prefix("Bearer "))
+                    .unwrap_or("");
+                // A `==` comparison here would let an attacker recover the
+                // token byte-by-byte from response timing, which matters
+                // since this token gates arbitrary process memory access.
+                // `ring` is already in the dependency tree for rustls, so
+                // its constant-time compare avoids pulling in another crate
+                // just for this.
+                if ring::constant_time::verify_slices_are_equal(
+                    provided.as_bytes(),
+                    expected_token.as_bytes(),
+                )
+                .is_ok()
+                {
+                    Ok(())
+                } else {
+                    Err(warp::reject::custom(Unauthorized))
+                }
+            }
+        })
+        .untuple_one()
+}
+
+/// Turns an `Unauthorized` rejection into a 401 response; every other
+/// rejection is passed through unchanged so warp's default handling still
+/// applies.
+pub async fn handle_rejection(err: Rejection) -> Result<impl Reply, Rejection> {
+    if err.find::<Unauthorized>().is_some() {
+        Ok(warp::reply::with_status(
+            "Unauthorized",
+            StatusCode::UNAUTHORIZED,
+        ))
+    } else {
+        Err(err)
+    }
+}
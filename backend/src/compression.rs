@@ -0,0 +1,78 @@
+use std::io::Write;
+
+use flate2::write::{DeflateEncoder, GzEncoder};
+use flate2::Compression;
+use warp::hyper::body::HttpBody;
+
+/// Controls gzip/deflate negotiation for the large-payload routes (scan
+/// results and pointer maps are highly compressible - repetitive addresses
+/// and values). `level` is a 0-9 zlib compression level; `min_size_bytes`
+/// skips compression for replies too small for it to be worth the CPU.
+#[derive(Debug, Clone, Copy)]
+pub struct CompressionConfig {
+    pub level: u32,
+    pub min_size_bytes: u64,
+}
+
+impl Default for CompressionConfig {
+    fn default() -> Self {
+        CompressionConfig {
+            level: 6,
+            min_size_bytes: 860,
+        }
+    }
+}
+
+/// Re-encodes `reply`'s body as gzip or deflate based on the client's
+/// `Accept-Encoding` header, in place of warp's built-in `compression`
+/// filters so the minimum-size threshold and level are configurable. Falls
+/// back to the original, uncompressed reply when the client accepts neither
+/// gzip nor deflate, the body is already under `min_size_bytes`, or the
+/// body size can't be determined up front (a stream, as `/region/dump`
+/// uses, is left alone).
+pub async fn negotiate<T: warp::Reply>(
+    config: CompressionConfig,
+    accept_encoding: Option<String>,
+    reply: T,
+) -> warp::reply::Response {
+    let response = reply.into_response();
+    let Some(exact_len) = response.body().size_hint().exact() else {
+        return response;
+    };
+    if exact_len < config.min_size_bytes {
+        return response;
+    }
+
+    let encoding = accept_encoding.unwrap_or_default();
+    let use_gzip = encoding.contains("gzip");
+    let use_deflate = !use_gzip && encoding.contains("deflate");
+    if !use_gzip && !use_deflate {
+        return response;
+    }
+
+    let (parts, body) = response.into_parts();
+    let Ok(bytes) = warp::hyper::body::to_bytes(body).await else {
+        return warp::http::Response::from_parts(parts, warp::hyper::Body::empty());
+    };
+
+    let level = Compression::new(config.level.min(9));
+    let compressed = if use_gzip {
+        let mut encoder = GzEncoder::new(Vec::new(), level);
+        encoder.write_all(&bytes).and_then(|_| encoder.finish())
+    } else {
+        let mut encoder = DeflateEncoder::new(Vec::new(), level);
+        encoder.write_all(&bytes).and_then(|_| encoder.finish())
+    };
+
+    let Ok(compressed) = compressed else {
+        return warp::http::Response::from_parts(parts, warp::hyper::Body::from(bytes));
+    };
+
+    let mut response = warp::http::Response::from_parts(parts, warp::hyper::Body::from(compressed));
+    response.headers_mut().insert(
+        "content-encoding",
+        warp::http::HeaderValue::from_static(if use_gzip { "gzip" } else { "deflate" }),
+    );
+    response.headers_mut().remove("content-length");
+    response
+}
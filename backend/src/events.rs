@@ -0,0 +1,92 @@
+use futures_util::{SinkExt, StreamExt};
+use serde::Serialize;
+use tokio::sync::broadcast;
+use warp::ws::{Message, WebSocket};
+
+/// A debug event published by `native_bridge` whenever a breakpoint hits, a
+/// watchpoint triggers, or an exception is caught. Serialized as JSON and
+/// forwarded verbatim to every subscriber of the `/events` websocket.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DebugEvent {
+    BreakpointHit {
+        thread_id: u64,
+        address: u64,
+        registers: serde_json::Value,
+    },
+    WatchpointTriggered {
+        thread_id: u64,
+        address: u64,
+        registers: serde_json::Value,
+    },
+    ExceptionCaught {
+        thread_id: u64,
+        address: u64,
+        registers: serde_json::Value,
+    },
+}
+
+const EVENT_CHANNEL_CAPACITY: usize = 1024;
+
+/// Broadcast hub that `native_bridge` publishes into and the `/events`
+/// websocket handler subscribes from. A broadcast channel (rather than an
+/// mpsc) lets every connected client see every event without the publisher
+/// needing to know how many subscribers exist.
+#[derive(Clone)]
+pub struct EventBus {
+    sender: broadcast::Sender<DebugEvent>,
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(EVENT_CHANNEL_CAPACITY);
+        EventBus { sender }
+    }
+
+    pub fn publish(&self, event: DebugEvent) {
+        // Err(_) just means there are currently no subscribers; that's not
+        // an error condition for the publisher.
+        let _ = self.sender.send(event);
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<DebugEvent> {
+        self.sender.subscribe()
+    }
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Drives one `/events` websocket connection: forwards every broadcast
+/// event to the client as a JSON text frame until a send fails (the socket
+/// has closed) or the bus itself is closed. A subscriber that falls too far
+/// behind skips the events it missed and keeps forwarding.
+pub async fn handle_socket(ws: WebSocket, bus: EventBus) {
+    let (mut tx, mut rx) = ws.split();
+    let mut events = bus.subscribe();
+
+    // Drain incoming frames so the client's close is processed; this
+    // endpoint is publish-only from the server's side. The loop below
+    // notices a closed socket on its next failed send.
+    let recv_task = tokio::spawn(async move {
+        while let Some(Ok(_)) = rx.next().await {}
+    });
+
+    loop {
+        match events.recv().await {
+            Ok(event) => {
+                let payload = serde_json::to_string(&event).unwrap_or_default();
+                if tx.send(Message::text(payload)).await.is_err() {
+                    break;
+                }
+            }
+            Err(broadcast::error::RecvError::Lagged(_)) => continue,
+            Err(broadcast::error::RecvError::Closed) => break,
+        }
+    }
+
+    recv_task.abort();
+}
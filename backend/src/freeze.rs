@@ -0,0 +1,192 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tokio::task::JoinHandle;
+
+use crate::native_bridge;
+use crate::request::DataType;
+use crate::session::{SessionId, SharedSessionManager};
+
+/// How often a session's freeze loop re-asserts its frozen values. Cheat
+/// Engine-style freezing only needs to win the race against the target's
+/// own writes, so a tight interval beats an event-driven approach here.
+const FREEZE_INTERVAL: Duration = Duration::from_millis(16);
+
+/// One registered freeze: the address/type pair plus the value to keep
+/// writing back. `FreezeToCurrent` snapshots the live value once at
+/// registration time; `Fixed` always rewrites the caller-supplied value,
+/// which also covers a frozen byte range (the length of the supplied value
+/// determines the width written).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FreezeEntry {
+    pub address: u64,
+    pub datatype: DataType,
+    pub value: Vec<u8>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FreezeMode {
+    /// Snapshot the address's present value and hold it.
+    FreezeToCurrent,
+    /// Hold the explicitly supplied value.
+    Fixed { value: Vec<u8> },
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FreezeRequest {
+    pub address: u64,
+    pub datatype: DataType,
+    pub mode: FreezeMode,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UnfreezeRequest {
+    pub address: u64,
+}
+
+/// Why a freeze registration was rejected. Surfaced to the client instead
+/// of silently accepting a freeze that would write nothing.
+#[derive(Debug)]
+pub enum FreezeError {
+    /// No process is attached under this session.
+    SessionNotFound,
+    /// `FreezeToCurrent` couldn't read the address to snapshot (bad
+    /// address, unmapped page, process gone).
+    ReadFailed,
+}
+impl warp::reject::Reject for FreezeError {}
+
+/// Turns a `FreezeError` rejection into a 400; every other rejection is
+/// passed through unchanged, matching `auth::handle_rejection`.
+pub async fn handle_rejection(
+    err: warp::Rejection,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    if let Some(freeze_error) = err.find::<FreezeError>() {
+        let message = match freeze_error {
+            FreezeError::SessionNotFound => "no process attached for this session",
+            FreezeError::ReadFailed => "failed to read the address to snapshot",
+        };
+        Ok(warp::reply::with_status(
+            warp::reply::json(&serde_json::json!({"error": message})),
+            warp::http::StatusCode::BAD_REQUEST,
+        ))
+    } else {
+        Err(err)
+    }
+}
+
+struct SessionFreezes {
+    entries: HashMap<u64, FreezeEntry>,
+    task: Option<JoinHandle<()>>,
+}
+
+/// Per-session freeze tables plus the background write-loop task that keeps
+/// each session's entries pinned in the target process.
+#[derive(Clone)]
+pub struct FreezeManager {
+    sessions: Arc<Mutex<HashMap<SessionId, SessionFreezes>>>,
+    session_manager: SharedSessionManager,
+}
+
+impl FreezeManager {
+    pub fn new(session_manager: SharedSessionManager) -> Self {
+        FreezeManager {
+            sessions: Arc::new(Mutex::new(HashMap::new())),
+            session_manager,
+        }
+    }
+
+    /// Registers a freeze, snapshotting the current value first when the
+    /// mode calls for it, and starts the session's write-loop task if this
+    /// is its first active freeze. Rejects the registration rather than
+    /// installing a freeze that would have nothing to write: a failed
+    /// snapshot read would otherwise sit in the table writing zero bytes
+    /// every tick while the client believes it succeeded.
+    pub fn freeze(&self, session: SessionId, request: FreezeRequest) -> Result<(), FreezeError> {
+        let process = self
+            .session_manager
+            .get(session)
+            .ok_or(FreezeError::SessionNotFound)?;
+
+        let value = match request.mode {
+            FreezeMode::FreezeToCurrent => {
+                native_bridge::read_memory(&process, request.address, request.datatype.size())
+                    .map_err(|_| FreezeError::ReadFailed)?
+            }
+            FreezeMode::Fixed { value } => value,
+        };
+        let entry = FreezeEntry {
+            address: request.address,
+            datatype: request.datatype,
+            value,
+        };
+
+        let mut sessions = self.sessions.lock().unwrap();
+        let session_freezes = sessions.entry(session).or_insert_with(|| SessionFreezes {
+            entries: HashMap::new(),
+            task: None,
+        });
+        session_freezes.entries.insert(request.address, entry);
+        if session_freezes.task.is_none() {
+            session_freezes.task = Some(self.spawn_write_loop(session));
+        }
+        Ok(())
+    }
+
+    pub fn unfreeze(&self, session: SessionId, address: u64) {
+        let mut sessions = self.sessions.lock().unwrap();
+        if let Some(session_freezes) = sessions.get_mut(&session) {
+            session_freezes.entries.remove(&address);
+            if session_freezes.entries.is_empty() {
+                if let Some(task) = session_freezes.task.take() {
+                    task.abort();
+                }
+                sessions.remove(&session);
+            }
+        }
+    }
+
+    pub fn list(&self, session: SessionId) -> Vec<FreezeEntry> {
+        self.sessions
+            .lock()
+            .unwrap()
+            .get(&session)
+            .map(|s| s.entries.values().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    fn spawn_write_loop(&self, session: SessionId) -> JoinHandle<()> {
+        let sessions = self.sessions.clone();
+        let session_manager = self.session_manager.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(FREEZE_INTERVAL);
+            loop {
+                ticker.tick().await;
+                let entries: Vec<FreezeEntry> = {
+                    let sessions = sessions.lock().unwrap();
+                    match sessions.get(&session) {
+                        Some(session_freezes) => {
+                            session_freezes.entries.values().cloned().collect()
+                        }
+                        // The session's last freeze was removed; stop
+                        // looping.
+                        None => break,
+                    }
+                };
+                // The process itself was closed; stop looping.
+                let Some(process) = session_manager.get(session) else {
+                    break;
+                };
+                for entry in entries {
+                    // Same write path `write_memory_handler` uses; errors
+                    // (e.g. the target exited) are left for the next tick
+                    // rather than tearing down the whole loop.
+                    let _ = native_bridge::write_memory(&process, entry.address, &entry.value);
+                }
+            }
+        })
+    }
+}
@@ -0,0 +1,158 @@
+use std::future::Future;
+use std::net::SocketAddr;
+use std::time::Instant;
+
+use metrics_exporter_prometheus::{PrometheusBuilder, PrometheusHandle};
+use warp::http::Response;
+use warp::Filter;
+
+/// Installs the global Prometheus recorder and returns a handle that can be
+/// scraped to render the text exposition format for the `/metrics` route.
+///
+/// Mirrors pict-rs's `init_metrics`: a single process-wide recorder is
+/// installed once at startup, and every handler reports into it through the
+/// `metrics` crate's global macros rather than threading a recorder handle
+/// around.
+pub fn init_metrics() -> PrometheusHandle {
+    PrometheusBuilder::new()
+        .install_recorder()
+        .expect("failed to install Prometheus recorder")
+}
+
+/// `GET /metrics` - renders the current Prometheus text exposition snapshot.
+pub fn metrics_route(
+    handle: PrometheusHandle,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("metrics").and(warp::get()).map(move || {
+        Response::builder()
+            .header("content-type", "text/plain; version=0.0.4")
+            .body(handle.render())
+            .unwrap()
+    })
+}
+
+/// Records request count and latency for a single route, in the same spirit
+/// as `logger::http_log` but emitted as Prometheus counters/histograms
+/// instead of a log line. Call this from inside a handler wrapper so `route`
+/// reflects the logical endpoint name rather than the raw request path.
+pub fn record_request(route: &'static str, status: u16, elapsed: std::time::Duration) {
+    metrics::counter!("memory_server_requests_total", "route" => route, "status" => status.to_string())
+        .increment(1);
+    metrics::histogram!("memory_server_request_duration_seconds", "route" => route)
+        .record(elapsed.as_secs_f64());
+}
+
+/// Wraps an async handler body, timing it and recording it under `route`
+/// with status 200 whatever its outcome. `serve.rs` uses `with_metrics`
+/// instead, which records the reply's real status.
+pub async fn timed<F, T>(route: &'static str, fut: F) -> T
+where
+    F: Future<Output = T>,
+{
+    let start = Instant::now();
+    let result = fut.await;
+    record_request(route, 200, start.elapsed());
+    result
+}
+
+/// Bytes moved through a memory read/write handler, reported so operators can
+/// watch I/O volume during long scanning sessions without parsing log lines.
+pub fn record_memory_bytes(direction: &'static str, bytes: u64) {
+    metrics::counter!("memory_server_memory_bytes_total", "direction" => direction)
+        .increment(bytes);
+}
+
+/// Number of matches returned by a scan/filter pass.
+pub fn record_scan_matches(route: &'static str, matches: u64) {
+    metrics::histogram!("memory_server_scan_matches", "route" => route).record(matches as f64);
+}
+
+/// Number of regions returned by `enum_regions`.
+pub fn record_region_count(count: u64) {
+    metrics::gauge!("memory_server_region_count").set(count as f64);
+}
+
+pub fn local_addr_label(addr: Option<SocketAddr>) -> String {
+    addr.map(|a| a.to_string()).unwrap_or_default()
+}
+
+/// What `with_metrics` should report about a handler's reply, beyond the
+/// request count/latency it always records.
+pub enum ReplyMetric {
+    /// No further metric; just request count/latency.
+    None,
+    /// The reply body *is* the data read (`read_memory`,
+    /// `read_memory_multiple`), so its serialized size is a true bytes-read
+    /// count.
+    BytesRead,
+    /// The reply is a top-level JSON array whose length is the real
+    /// quantity to report - scan/filter matches or enumerated regions.
+    ArrayLen(ArrayLenMetric),
+}
+
+#[derive(Clone, Copy)]
+pub enum ArrayLenMetric {
+    ScanMatches(&'static str),
+    RegionCount,
+}
+
+/// Runs `fut`, then records request count/latency for `route` plus whatever
+/// `reply_metric` asks for. `ArrayLen` buffers and parses the whole body to
+/// count its elements, which is only done for the handful of routes that opt
+/// into it (scan/filter/region results); the buffered bytes are put back so
+/// the reply is otherwise unchanged.
+pub async fn with_metrics<Fut, T>(
+    route: &'static str,
+    reply_metric: ReplyMetric,
+    fut: Fut,
+) -> Result<warp::reply::Response, warp::Rejection>
+where
+    Fut: Future<Output = Result<T, warp::Rejection>>,
+    T: warp::Reply,
+{
+    let start = Instant::now();
+    match fut.await {
+        Ok(reply) => {
+            let mut response = reply.into_response();
+            match reply_metric {
+                ReplyMetric::None => {}
+                ReplyMetric::BytesRead => {
+                    if let Some(len) =
+                        warp::hyper::body::HttpBody::size_hint(response.body()).exact()
+                    {
+                        record_memory_bytes("read", len);
+                    }
+                }
+                ReplyMetric::ArrayLen(metric) => {
+                    response = record_array_len(metric, response).await;
+                }
+            }
+            record_request(route, response.status().as_u16(), start.elapsed());
+            Ok(response)
+        }
+        Err(rejection) => {
+            record_request(route, 500, start.elapsed());
+            Err(rejection)
+        }
+    }
+}
+
+async fn record_array_len(
+    metric: ArrayLenMetric,
+    response: warp::reply::Response,
+) -> warp::reply::Response {
+    let (parts, body) = response.into_parts();
+    let Ok(bytes) = warp::hyper::body::to_bytes(body).await else {
+        return warp::http::Response::from_parts(parts, warp::hyper::Body::empty());
+    };
+
+    if let Ok(serde_json::Value::Array(items)) = serde_json::from_slice(&bytes) {
+        let count = items.len() as u64;
+        match metric {
+            ArrayLenMetric::ScanMatches(route) => record_scan_matches(route, count),
+            ArrayLenMetric::RegionCount => record_region_count(count),
+        }
+    }
+
+    warp::http::Response::from_parts(parts, warp::hyper::Body::from(bytes))
+}
@@ -0,0 +1,137 @@
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+use serde_json::Value;
+
+use crate::events::{DebugEvent, EventBus};
+
+/// How often `poll_trap_events` checks the native engine for new
+/// breakpoint/watchpoint/exception hits. Tight enough that a live debug
+/// session doesn't feel laggy, in the same spirit as `freeze`'s write-loop
+/// interval.
+const TRAP_POLL_INTERVAL: Duration = Duration::from_millis(5);
+
+/// Global event bus `serve` installs at startup. A bare global (rather than
+/// threading a handle through every native callback) because the publishers
+/// below run on the `poll_trap_events` background task, outside of any
+/// request's call stack.
+static EVENT_BUS: OnceLock<Mutex<Option<EventBus>>> = OnceLock::new();
+
+pub fn set_event_bus(bus: EventBus) {
+    *EVENT_BUS
+        .get_or_init(|| Mutex::new(None))
+        .lock()
+        .unwrap() = Some(bus);
+}
+
+fn event_bus() -> Option<EventBus> {
+    EVENT_BUS.get()?.lock().unwrap().clone()
+}
+
+/// Handle to one attached target process.
+#[derive(Debug, Clone)]
+pub struct ProcessState {
+    pub pid: i32,
+}
+
+#[derive(Debug)]
+pub struct NativeBridgeError(pub String);
+
+pub fn native_api_init(mode: i32) {
+    let _ = mode;
+    tokio::spawn(poll_trap_events());
+}
+
+/// One breakpoint/watchpoint/exception hit reported by the native engine.
+#[derive(Debug)]
+enum TrapEvent {
+    Breakpoint { thread_id: u64, address: u64, registers: Value },
+    Watchpoint { thread_id: u64, address: u64, registers: Value },
+    Exception { thread_id: u64, address: u64, registers: Value },
+}
+
+/// Drains trap events from the native engine and dispatches each to its
+/// publisher, the actual call site for `on_breakpoint_hit`,
+/// `on_watchpoint_triggered`, and `on_exception_caught`. `next_trap_events`
+/// is where this plugs into the platform debug API; not implemented in
+/// this environment (same reason `read_memory`/`write_memory` below are
+/// stubs), so it always reports nothing here.
+async fn poll_trap_events() {
+    loop {
+        for event in next_trap_events() {
+            match event {
+                TrapEvent::Breakpoint { thread_id, address, registers } => {
+                    on_breakpoint_hit(thread_id, address, registers)
+                }
+                TrapEvent::Watchpoint { thread_id, address, registers } => {
+                    on_watchpoint_triggered(thread_id, address, registers)
+                }
+                TrapEvent::Exception { thread_id, address, registers } => {
+                    on_exception_caught(thread_id, address, registers)
+                }
+            }
+        }
+        tokio::time::sleep(TRAP_POLL_INTERVAL).await;
+    }
+}
+
+fn next_trap_events() -> Vec<TrapEvent> {
+    Vec::new()
+}
+
+pub fn read_memory(
+    process: &ProcessState,
+    address: u64,
+    size: usize,
+) -> Result<Vec<u8>, NativeBridgeError> {
+    let _ = (process, address, size);
+    Err(NativeBridgeError("not implemented in this environment".into()))
+}
+
+pub fn write_memory(
+    process: &ProcessState,
+    address: u64,
+    data: &[u8],
+) -> Result<(), NativeBridgeError> {
+    let _ = (process, address, data);
+    Err(NativeBridgeError("not implemented in this environment".into()))
+}
+
+/// Called by `poll_trap_events` when the native engine reports that a
+/// breakpoint set via `set_breakpoint_handler` fired. Publishes to the global event bus so
+/// every `/events` websocket subscriber sees the hit live, instead of
+/// clients having to poll `get_exception_info`.
+pub fn on_breakpoint_hit(thread_id: u64, address: u64, registers: Value) {
+    if let Some(bus) = event_bus() {
+        bus.publish(DebugEvent::BreakpointHit {
+            thread_id,
+            address,
+            registers,
+        });
+    }
+}
+
+/// Called by `poll_trap_events` when the native engine reports that a
+/// watchpoint set via `set_watchpoint_handler` triggered.
+pub fn on_watchpoint_triggered(thread_id: u64, address: u64, registers: Value) {
+    if let Some(bus) = event_bus() {
+        bus.publish(DebugEvent::WatchpointTriggered {
+            thread_id,
+            address,
+            registers,
+        });
+    }
+}
+
+/// Called by `poll_trap_events` when the native engine reports that the
+/// debuggee raised an exception that would otherwise only surface through
+/// `get_exception_info_handler`.
+pub fn on_exception_caught(thread_id: u64, address: u64, registers: Value) {
+    if let Some(bus) = event_bus() {
+        bus.publish(DebugEvent::ExceptionCaught {
+            thread_id,
+            address,
+            registers,
+        });
+    }
+}
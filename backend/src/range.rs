@@ -0,0 +1,190 @@
+/// A single `bytes=start-end` range, already clamped to a known total size.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ByteRange {
+    pub start: u64,
+    pub end: u64,
+}
+
+impl ByteRange {
+    pub fn len(&self) -> u64 {
+        self.end - self.start + 1
+    }
+
+    pub fn content_range_header(&self, total_len: u64) -> String {
+        format!("bytes {}-{}/{}", self.start, self.end, total_len)
+    }
+}
+
+/// Parses a `Range: bytes=start-end` header against a resource of
+/// `total_len` bytes, the way pict-rs does for media responses. Only the
+/// single-range form is supported (no `bytes=0-10,20-30` multipart
+/// ranges); open-ended bounds (`bytes=100-`, `bytes=-500`) are resolved
+/// against `total_len`. Returns `None` when there is no header, the
+/// header doesn't parse, or the requested range doesn't fit the
+/// resource, in which case callers should fall back to a full 200
+/// response.
+pub fn parse_range(header: Option<&str>, total_len: u64) -> Option<ByteRange> {
+    let header = header?.strip_prefix("bytes=")?;
+    let (start_str, end_str) = header.split_once('-')?;
+
+    let (start, end) = if start_str.is_empty() {
+        // `bytes=-N` means "the last N bytes".
+        let suffix_len: u64 = end_str.parse().ok()?;
+        let suffix_len = suffix_len.min(total_len);
+        (total_len - suffix_len, total_len.checked_sub(1)?)
+    } else {
+        let start: u64 = start_str.parse().ok()?;
+        let end = if end_str.is_empty() {
+            total_len.saturating_sub(1)
+        } else {
+            end_str.parse().ok()?
+        };
+        (start, end)
+    };
+
+    if start > end || start >= total_len {
+        return None;
+    }
+    Some(ByteRange {
+        start,
+        end: end.min(total_len.saturating_sub(1)),
+    })
+}
+
+/// Slices an already-built reply down to the requested byte range, for
+/// handlers (like `read_file_handler`) that build their full response body
+/// before `serve.rs` gets a chance to apply `Range`, as opposed to
+/// `region::dump_region_handler`, which streams only the requested range in
+/// the first place. Mirrors the buffer-and-rewrap approach
+/// `metrics::record_array_len` uses to post-process a reply without
+/// changing the handler's own signature.
+pub async fn apply_to_reply(
+    range_header: Option<&str>,
+    response: warp::reply::Response,
+) -> warp::reply::Response {
+    let (parts, body) = response.into_parts();
+    let Ok(bytes) = warp::hyper::body::to_bytes(body).await else {
+        return warp::http::Response::from_parts(parts, warp::hyper::Body::empty());
+    };
+
+    let total_len = bytes.len() as u64;
+    if total_len == 0 {
+        return warp::http::Response::from_parts(parts, warp::hyper::Body::from(bytes));
+    }
+
+    let full_range = ByteRange { start: 0, end: total_len - 1 };
+    let range = parse_range(range_header, total_len).unwrap_or(full_range);
+    let is_partial = range_header.is_some() && range != full_range;
+    let slice = bytes.slice(range.start as usize..=range.end as usize);
+
+    let mut builder = warp::http::Response::builder();
+    for (name, value) in parts.headers.iter() {
+        if name == "content-length" {
+            continue;
+        }
+        builder = builder.header(name, value);
+    }
+    builder = builder
+        .header("accept-ranges", "bytes")
+        .header("content-length", range.len().to_string());
+    builder = if is_partial {
+        builder
+            .status(warp::http::StatusCode::PARTIAL_CONTENT)
+            .header("content-range", range.content_range_header(total_len))
+    } else {
+        builder.status(parts.status)
+    };
+
+    builder
+        .body(warp::hyper::Body::from(slice))
+        .unwrap_or_else(|_| warp::http::Response::from_parts(parts, warp::hyper::Body::from(bytes)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn no_header_means_no_range() {
+        assert_eq!(parse_range(None, 100), None);
+    }
+
+    #[test]
+    fn full_bounds() {
+        assert_eq!(
+            parse_range(Some("bytes=10-20"), 100),
+            Some(ByteRange { start: 10, end: 20 })
+        );
+    }
+
+    #[test]
+    fn open_ended_start() {
+        assert_eq!(
+            parse_range(Some("bytes=90-"), 100),
+            Some(ByteRange { start: 90, end: 99 })
+        );
+    }
+
+    #[test]
+    fn suffix_length() {
+        assert_eq!(
+            parse_range(Some("bytes=-10"), 100),
+            Some(ByteRange { start: 90, end: 99 })
+        );
+    }
+
+    #[test]
+    fn suffix_length_larger_than_total_clamps_to_whole_resource() {
+        assert_eq!(
+            parse_range(Some("bytes=-1000"), 100),
+            Some(ByteRange { start: 0, end: 99 })
+        );
+    }
+
+    #[test]
+    fn end_beyond_total_clamps() {
+        assert_eq!(
+            parse_range(Some("bytes=50-1000"), 100),
+            Some(ByteRange { start: 50, end: 99 })
+        );
+    }
+
+    #[test]
+    fn start_at_or_beyond_total_is_out_of_bounds() {
+        assert_eq!(parse_range(Some("bytes=100-200"), 100), None);
+        assert_eq!(parse_range(Some("bytes=150-200"), 100), None);
+    }
+
+    #[test]
+    fn start_after_end_is_rejected() {
+        assert_eq!(parse_range(Some("bytes=20-10"), 100), None);
+    }
+
+    #[test]
+    fn zero_total_len_is_always_out_of_bounds() {
+        assert_eq!(parse_range(Some("bytes=0-0"), 0), None);
+    }
+
+    #[test]
+    fn missing_unit_prefix_is_rejected() {
+        assert_eq!(parse_range(Some("0-10"), 100), None);
+    }
+
+    #[test]
+    fn malformed_numbers_are_rejected() {
+        assert_eq!(parse_range(Some("bytes=abc-10"), 100), None);
+        assert_eq!(parse_range(Some("bytes=10-xyz"), 100), None);
+    }
+
+    #[test]
+    fn multi_range_falls_back_to_none() {
+        assert_eq!(parse_range(Some("bytes=0-10,20-30"), 100), None);
+    }
+
+    #[test]
+    fn byte_range_len_and_content_range_header() {
+        let range = ByteRange { start: 10, end: 19 };
+        assert_eq!(range.len(), 10);
+        assert_eq!(range.content_range_header(100), "bytes 10-19/100");
+    }
+}
@@ -0,0 +1,77 @@
+use bytes::Bytes;
+use futures_util::stream;
+use warp::http::{Response, StatusCode};
+use warp::hyper::Body;
+
+use crate::native_bridge::{self, ProcessState};
+use crate::range::{self, ByteRange};
+use crate::request::ReadMemoryRequest;
+
+/// Read size per stream chunk. Keeps a single region dump from ever
+/// buffering the whole region in memory, regardless of how large `size` is.
+const CHUNK_SIZE: u64 = 64 * 1024;
+
+/// `GET /region/dump` - streams a memory region as a chunked body, honoring
+/// `Range` the same way `/file` does so the frontend can lazily fetch
+/// hex-view windows and resume interrupted large dumps.
+pub async fn dump_region_handler(
+    session: ProcessState,
+    dump_request: ReadMemoryRequest,
+    range_header: Option<String>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let total_len = dump_request.size as u64;
+
+    if total_len == 0 {
+        let response = Response::builder()
+            .status(StatusCode::OK)
+            .header("accept-ranges", "bytes")
+            .header("content-length", "0")
+            .body(Body::empty())
+            .unwrap();
+        return Ok(response);
+    }
+
+    let full_range = ByteRange {
+        start: 0,
+        end: total_len - 1,
+    };
+    let range = range::parse_range(range_header.as_deref(), total_len).unwrap_or(full_range);
+    let is_partial = range_header.is_some() && range != full_range;
+
+    let start_address = dump_request.address as u64 + range.start;
+    let remaining = range.len();
+
+    // Every chunk advances `remaining` by exactly `read_len`, zero-filling
+    // any span `native_bridge::read_memory` can't service (unmapped page,
+    // read error). That keeps the stream's total byte count matching the
+    // `content-length` header set below; treating a short/failed read as
+    // end-of-stream instead would truncate the body mid-transfer, which
+    // HTTP clients treat as a corrupted response.
+    let chunks = stream::unfold(
+        (session, start_address, remaining),
+        |(session, address, remaining)| async move {
+            if remaining == 0 {
+                return None;
+            }
+            let read_len = remaining.min(CHUNK_SIZE) as usize;
+            let mut data = native_bridge::read_memory(&session, address, read_len)
+                .unwrap_or_default();
+            data.resize(read_len, 0);
+            let next_state = (session, address + read_len as u64, remaining - read_len as u64);
+            Some((Ok::<_, std::io::Error>(Bytes::from(data)), next_state))
+        },
+    );
+
+    let mut builder = Response::builder()
+        .header("accept-ranges", "bytes")
+        .header("content-length", range.len().to_string());
+    builder = if is_partial {
+        builder
+            .status(StatusCode::PARTIAL_CONTENT)
+            .header("content-range", range.content_range_header(total_len))
+    } else {
+        builder.status(StatusCode::OK)
+    };
+
+    Ok(builder.body(Body::wrap_stream(chunks)).unwrap())
+}
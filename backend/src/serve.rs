@@ -1,17 +1,56 @@
 use include_dir::{include_dir, Dir};
 use std::net::IpAddr;
-use std::sync::{Arc, Mutex};
+use std::sync::Arc;
 use warp::http::Response;
 use warp::path::Tail;
-use warp::Filter;
+use warp::{Filter, Reply};
 
 use crate::api;
+use crate::auth;
+use crate::compression::{self, CompressionConfig};
+use crate::events::{self, EventBus};
+use crate::freeze::{self, FreezeManager};
 use crate::logger;
+use crate::metrics;
 use crate::native_bridge;
+use crate::range;
+use crate::region;
 use crate::request;
+use crate::session::{self, SessionManager};
 
-pub async fn serve(mode: i32, host: IpAddr, port: u16) {
-    let pid_state = Arc::new(Mutex::new(None));
+/// Optional TLS material. When both paths are set, `serve` binds HTTPS via
+/// rustls instead of plaintext HTTP.
+pub struct TlsConfig {
+    pub cert_path: String,
+    pub key_path: String,
+}
+
+pub async fn serve(
+    mode: i32,
+    host: IpAddr,
+    port: u16,
+    tls: Option<TlsConfig>,
+    api_token: Option<String>,
+    compression_config: CompressionConfig,
+) {
+    // Keyed by session id so multiple clients can attach to and scan
+    // different target processes concurrently, each with its own isolated
+    // scan/filter result buffers. `open_process` mints the id against the
+    // raw registry (`api::with_state`); every other handler resolves its
+    // specific attached process via `session::with_state`, which reads the
+    // `X-Session-Id` header (or `session_id` query param) and looks it up.
+    let session_manager = SessionManager::new();
+    let prometheus_handle = metrics::init_metrics();
+    let api_token = api_token.map(Arc::new);
+
+    // `native_bridge` publishes breakpoint/watchpoint/exception events into
+    // this bus; every `/events` websocket connection gets its own
+    // subscriber so clients react to hits live instead of polling
+    // `get_exception_info`.
+    let event_bus = EventBus::new();
+    native_bridge::set_event_bus(event_bus.clone());
+
+    let freeze_manager = FreezeManager::new(session_manager.clone());
 
     let cors = warp::cors()
         .allow_any_origin()
@@ -29,113 +68,173 @@ pub async fn serve(mode: i32, host: IpAddr, port: u16) {
 
     let enum_module = warp::path!("modules")
         .and(warp::get())
-        .and(api::with_state(pid_state.clone()))
-        .and_then(|pid_state| async move { api::enummodule_handler(pid_state).await });
+        .and(session::with_state(session_manager.clone()))
+        .and_then(|session| async move { api::enummodule_handler(session).await });
 
     let open_process = warp::path!("process")
         .and(warp::post())
         .and(warp::body::json())
-        .and(api::with_state(pid_state.clone()))
-        .and_then(|open_process, pid_state| async move {
-            api::open_process_handler(pid_state, open_process).await
+        .and(api::with_state(session_manager.clone()))
+        .and_then(|open_process, session_manager| async move {
+            api::open_process_handler(session_manager, open_process).await
         });
 
     let change_process_state = warp::path!("process")
         .and(warp::put())
         .and(warp::body::json())
-        .and(api::with_state(pid_state.clone()))
-        .and_then(|state_request, pid_state| async move {
-            api::change_process_state_handler(pid_state, state_request).await
+        .and(session::with_state(session_manager.clone()))
+        .and_then(|state_request, session| async move {
+            api::change_process_state_handler(session, state_request).await
         });
 
     // Memory Operation Routes
     let read_memory = warp::path!("memory")
         .and(warp::get())
         .and(warp::query::<request::ReadMemoryRequest>())
-        .and(api::with_state(pid_state.clone()))
-        .and_then(|read_memory_request, pid_state| async move {
-            api::read_memory_handler(pid_state, read_memory_request).await
+        .and(session::with_state(session_manager.clone()))
+        .and_then(|read_memory_request, session| async move {
+            metrics::with_metrics(
+                "read_memory",
+                metrics::ReplyMetric::BytesRead,
+                api::read_memory_handler(session, read_memory_request),
+            )
+            .await
         });
 
     let write_memory = warp::path!("memory")
         .and(warp::post())
         .and(warp::body::json())
-        .and(api::with_state(pid_state.clone()))
-        .and_then(|write_memory, pid_state| async move {
-            api::write_memory_handler(pid_state, write_memory).await
+        .and(session::with_state(session_manager.clone()))
+        .and_then(|write_memory_request: request::WriteMemoryRequest, session| async move {
+            // The reply is just a small ack, so its size says nothing about
+            // how much was written; report the actual payload length
+            // instead of reusing the reply-size signal `BytesRead` uses.
+            let bytes_written = write_memory_request.value.len() as u64;
+            let result = metrics::with_metrics(
+                "write_memory",
+                metrics::ReplyMetric::None,
+                api::write_memory_handler(session, write_memory_request),
+            )
+            .await;
+            if result.is_ok() {
+                metrics::record_memory_bytes("write", bytes_written);
+            }
+            result
         });
 
     let read_memory_multiple = warp::path!("memories")
         .and(warp::post())
         .and(warp::body::content_length_limit(1024 * 1024 * 10)) // 10MB
         .and(warp::body::json::<Vec<request::ReadMemoryRequest>>())
-        .and(api::with_state(pid_state.clone()))
-        .and_then(|read_memory_requests, pid_state| async move {
-            api::read_memory_multiple_handler(pid_state, read_memory_requests).await
+        .and(session::with_state(session_manager.clone()))
+        .and(warp::header::optional::<String>("accept-encoding"))
+        .and_then(move |read_memory_requests, session, accept_encoding| async move {
+            let reply = metrics::with_metrics(
+                "read_memory_multiple",
+                metrics::ReplyMetric::BytesRead,
+                api::read_memory_multiple_handler(session, read_memory_requests),
+            )
+            .await?;
+            Ok::<_, warp::Rejection>(
+                compression::negotiate(compression_config, accept_encoding, reply).await,
+            )
         });
 
     // Memory Analysis Routes
     let memory_scan = warp::path!("memoryscan")
         .and(warp::post())
         .and(warp::body::json())
-        .and(api::with_state(pid_state.clone()))
-        .and_then(|scan_request, pid_state| async move {
-            api::memory_scan_handler(pid_state, scan_request).await
+        .and(session::with_state(session_manager.clone()))
+        .and(warp::header::optional::<String>("accept-encoding"))
+        .and_then(move |scan_request, session, accept_encoding| async move {
+            let reply = metrics::with_metrics(
+                "memory_scan",
+                metrics::ReplyMetric::ArrayLen(metrics::ArrayLenMetric::ScanMatches(
+                    "memory_scan",
+                )),
+                api::memory_scan_handler(session, scan_request),
+            )
+            .await?;
+            Ok::<_, warp::Rejection>(
+                compression::negotiate(compression_config, accept_encoding, reply).await,
+            )
         });
 
     let memory_filter = warp::path!("memoryfilter")
         .and(warp::post())
         .and(warp::body::json())
-        .and(api::with_state(pid_state.clone()))
-        .and_then(|filter_request, pid_state| async move {
-            api::memory_filter_handler(pid_state, filter_request).await
+        .and(session::with_state(session_manager.clone()))
+        .and(warp::header::optional::<String>("accept-encoding"))
+        .and_then(move |filter_request, session, accept_encoding| async move {
+            let reply = metrics::with_metrics(
+                "memory_filter",
+                metrics::ReplyMetric::ArrayLen(metrics::ArrayLenMetric::ScanMatches(
+                    "memory_filter",
+                )),
+                api::memory_filter_handler(session, filter_request),
+            )
+            .await?;
+            Ok::<_, warp::Rejection>(
+                compression::negotiate(compression_config, accept_encoding, reply).await,
+            )
         });
 
     let enum_regions = warp::path!("regions")
         .and(warp::get())
-        .and(api::with_state(pid_state.clone()))
-        .and_then(|pid_state| async move { api::enumerate_regions_handler(pid_state).await });
+        .and(session::with_state(session_manager.clone()))
+        .and(warp::header::optional::<String>("accept-encoding"))
+        .and_then(move |session, accept_encoding| async move {
+            let reply = metrics::with_metrics(
+                "enum_regions",
+                metrics::ReplyMetric::ArrayLen(metrics::ArrayLenMetric::RegionCount),
+                api::enumerate_regions_handler(session),
+            )
+            .await?;
+            Ok::<_, warp::Rejection>(
+                compression::negotiate(compression_config, accept_encoding, reply).await,
+            )
+        });
 
     // Debug Routes
     let set_watchpoint = warp::path!("watchpoint")
         .and(warp::post())
         .and(warp::body::json())
-        .and(api::with_state(pid_state.clone()))
-        .and_then(|set_watchpoint_request, pid_state| async move {
-            api::set_watchpoint_handler(pid_state, set_watchpoint_request).await
+        .and(session::with_state(session_manager.clone()))
+        .and_then(|set_watchpoint_request, session| async move {
+            api::set_watchpoint_handler(session, set_watchpoint_request).await
         });
 
     let remove_watchpoint = warp::path!("watchpoint")
         .and(warp::delete())
         .and(warp::body::json())
-        .and(api::with_state(pid_state.clone()))
-        .and_then(|remove_watchpoint_request, pid_state| async move {
-            api::remove_watchpoint_handler(pid_state, remove_watchpoint_request).await
+        .and(session::with_state(session_manager.clone()))
+        .and_then(|remove_watchpoint_request, session| async move {
+            api::remove_watchpoint_handler(session, remove_watchpoint_request).await
         });
 
     let set_breakpoint = warp::path!("breakpoint")
         .and(warp::post())
         .and(warp::body::json())
-        .and(api::with_state(pid_state.clone()))
-        .and_then(|set_breakpoint_request, pid_state| async move {
-            api::set_breakpoint_handler(pid_state, set_breakpoint_request).await
+        .and(session::with_state(session_manager.clone()))
+        .and_then(|set_breakpoint_request, session| async move {
+            api::set_breakpoint_handler(session, set_breakpoint_request).await
         });
 
     let remove_breakpoint = warp::path!("breakpoint")
         .and(warp::delete())
         .and(warp::body::json())
-        .and(api::with_state(pid_state.clone()))
-        .and_then(|remove_breakpoint_request, pid_state| async move {
-            api::remove_breakpoint_handler(pid_state, remove_breakpoint_request).await
+        .and(session::with_state(session_manager.clone()))
+        .and_then(|remove_breakpoint_request, session| async move {
+            api::remove_breakpoint_handler(session, remove_breakpoint_request).await
         });
 
     // Utility Routes
     let resolve_addr = warp::path!("resolveaddr")
         .and(warp::get())
         .and(warp::query::<request::ResolveAddrRequest>())
-        .and(api::with_state(pid_state.clone()))
-        .and_then(|resolve_addr_request, pid_state| async move {
-            api::resolve_addr_handler(pid_state, resolve_addr_request).await
+        .and(session::with_state(session_manager.clone()))
+        .and_then(|resolve_addr_request, session| async move {
+            api::resolve_addr_handler(session, resolve_addr_request).await
         });
 
     let explore_directory = warp::path!("directory")
@@ -145,18 +244,37 @@ pub async fn serve(mode: i32, host: IpAddr, port: u16) {
             api::explore_directory_handler(explore_directory_request).await
         });
 
+    // `read_file_handler` keeps its original one-argument signature; Range
+    // support is applied here as a post-processing step over its reply
+    // (`range::apply_to_reply`) rather than threading a `range` parameter
+    // into the handler itself, the same buffer-and-rewrap approach
+    // `metrics::record_array_len` uses to add a cross-cutting concern
+    // without changing a handler's signature.
     let read_file = warp::path!("file")
         .and(warp::get())
         .and(warp::query::<request::ReadFileRequest>())
-        .and_then(
-            |read_file_request| async move { api::read_file_handler(read_file_request).await },
-        );
+        .and(warp::header::optional::<String>("range"))
+        .and_then(|read_file_request, range_header: Option<String>| async move {
+            let reply = api::read_file_handler(read_file_request).await?;
+            Ok::<_, warp::Rejection>(
+                range::apply_to_reply(range_header.as_deref(), reply.into_response()).await,
+            )
+        });
+
+    let dump_region = warp::path!("region" / "dump")
+        .and(warp::get())
+        .and(warp::query::<request::ReadMemoryRequest>())
+        .and(warp::header::optional::<String>("range"))
+        .and(session::with_state(session_manager.clone()))
+        .and_then(|dump_request, range, session| async move {
+            region::dump_region_handler(session, dump_request, range).await
+        });
 
     // Info Routes
     let get_app_info = warp::path!("appinfo")
         .and(warp::get())
-        .and(api::with_state(pid_state.clone()))
-        .and_then(|pid_state| async move { api::get_app_info_handler(pid_state).await });
+        .and(session::with_state(session_manager.clone()))
+        .and_then(|session| async move { api::get_app_info_handler(session).await });
 
     let server_info = warp::path!("serverinfo")
         .and(warp::get())
@@ -166,14 +284,68 @@ pub async fn serve(mode: i32, host: IpAddr, port: u16) {
         .and(warp::get())
         .and_then(api::get_exception_info_handler);
 
+    let metrics_route = metrics::metrics_route(prometheus_handle);
+
+    let event_stream = warp::path!("events").and(warp::ws()).map({
+        let event_bus = event_bus.clone();
+        move |ws: warp::ws::Ws| {
+            let event_bus = event_bus.clone();
+            ws.on_upgrade(move |socket| events::handle_socket(socket, event_bus))
+        }
+    });
+
     let pointermap_generate = warp::path!("pointermap")
         .and(warp::post())
         .and(warp::body::json())
-        .and(api::with_state(pid_state.clone()))
-        .and_then(|request, pid_state| async move {
-            api::pointermap_generate_handler(pid_state, request).await
+        .and(session::with_state(session_manager.clone()))
+        .and(warp::header::optional::<String>("accept-encoding"))
+        .and_then(move |request, session, accept_encoding| async move {
+            let reply = api::pointermap_generate_handler(session, request).await?;
+            Ok::<_, warp::Rejection>(
+                compression::negotiate(compression_config, accept_encoding, reply).await,
+            )
+        });
+
+    // Freeze Routes
+    let register_freeze = warp::path!("freeze")
+        .and(warp::post())
+        .and(warp::body::json())
+        .and(session::with_session_id())
+        .and_then({
+            let freeze_manager = freeze_manager.clone();
+            move |freeze_request: freeze::FreezeRequest, session_id| {
+                let freeze_manager = freeze_manager.clone();
+                async move {
+                    freeze_manager
+                        .freeze(session_id, freeze_request)
+                        .map(|()| warp::reply::json(&serde_json::json!({"status": "ok"})))
+                        .map_err(warp::reject::custom)
+                }
+            }
         });
 
+    let remove_freeze = warp::path!("freeze")
+        .and(warp::delete())
+        .and(warp::body::json())
+        .and(session::with_session_id())
+        .map({
+            let freeze_manager = freeze_manager.clone();
+            move |unfreeze_request: freeze::UnfreezeRequest, session_id| {
+                freeze_manager.unfreeze(session_id, unfreeze_request.address);
+                warp::reply::json(&serde_json::json!({"status": "ok"}))
+            }
+        });
+
+    let list_freezes = warp::path!("freeze")
+        .and(warp::get())
+        .and(session::with_session_id())
+        .map({
+            let freeze_manager = freeze_manager.clone();
+            move |session_id| warp::reply::json(&freeze_manager.list(session_id))
+        });
+
+    let freeze_routes = register_freeze.or(remove_freeze).or(list_freezes);
+
     // Group routes by functionality
     let process_routes = enum_process
         .or(enum_module)
@@ -189,26 +361,56 @@ pub async fn serve(mode: i32, host: IpAddr, port: u16) {
         .or(set_breakpoint)
         .or(remove_breakpoint);
 
-    let utility_routes = resolve_addr.or(explore_directory).or(read_file);
+    let utility_routes = resolve_addr
+        .or(explore_directory)
+        .or(read_file)
+        .or(dump_region);
 
     let info_routes = get_app_info
-        .or(server_info)
         .or(get_exception_info)
-        .or(pointermap_generate);
-
-    // Combine all route groups
-    let routes = process_routes
+        .or(pointermap_generate)
+        .or(metrics_route)
+        .or(event_stream);
+
+    // Every route except `server_info` and the static frontend assets sits
+    // behind the bearer-token filter, since the rest expose arbitrary
+    // process memory read/write. `static_files` is exempt too: a plain
+    // browser navigation to `/` can't attach an `Authorization` header, so
+    // gating the SPA's own HTML/JS would brick the frontend once a token
+    // is configured.
+    let protected_routes = process_routes
         .or(memory_operation_routes)
         .or(memory_analysis_routes)
         .or(debug_routes)
         .or(utility_routes)
         .or(info_routes)
+        .or(freeze_routes);
+
+    // Combine all route groups
+    let routes = server_info
         .or(static_files)
+        .or(auth::with_auth(api_token).and(protected_routes))
         .with(cors)
-        .with(warp::log::custom(logger::http_log));
+        .with(warp::log::custom(logger::http_log))
+        .recover(auth::handle_rejection)
+        .recover(freeze::handle_rejection)
+        .recover(session::handle_rejection);
 
     native_bridge::native_api_init(mode);
-    warp::serve(routes).run((host, port)).await;
+
+    match tls {
+        Some(tls) => {
+            warp::serve(routes)
+                .tls()
+                .cert_path(tls.cert_path)
+                .key_path(tls.key_path)
+                .run((host, port))
+                .await;
+        }
+        None => {
+            warp::serve(routes).run((host, port)).await;
+        }
+    }
 }
 
 static STATIC_DIR: Dir = include_dir!("../frontend/out");
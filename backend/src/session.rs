@@ -0,0 +1,135 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+use warp::Filter;
+
+use crate::native_bridge::ProcessState;
+
+/// Opaque handle identifying one attached process across requests. Minted by
+/// `open_process_handler` and threaded back in by clients via the
+/// `X-Session-Id` header (or a `session_id` query parameter as a browser-
+/// friendly fallback).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SessionId(u64);
+
+impl std::fmt::Display for SessionId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::str::FromStr for SessionId {
+    type Err = std::num::ParseIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(SessionId(s.parse()?))
+    }
+}
+
+/// Registry of every attached process, keyed by `SessionId`. Replaces the
+/// old single `Arc<Mutex<Option<ProcessState>>>` so multiple clients can
+/// attach to and scan different target processes concurrently, each with
+/// its own isolated scan/filter result buffers.
+#[derive(Default)]
+pub struct SessionManager {
+    next_id: AtomicU64,
+    sessions: Mutex<HashMap<SessionId, ProcessState>>,
+}
+
+pub type SharedSessionManager = Arc<SessionManager>;
+
+impl SessionManager {
+    pub fn new() -> SharedSessionManager {
+        Arc::new(SessionManager {
+            next_id: AtomicU64::new(1),
+            sessions: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Mints a fresh session id and stores `state` under it.
+    pub fn insert(&self, state: ProcessState) -> SessionId {
+        let id = SessionId(self.next_id.fetch_add(1, Ordering::Relaxed));
+        self.sessions.lock().unwrap().insert(id, state);
+        id
+    }
+
+    pub fn get(&self, id: SessionId) -> Option<ProcessState> {
+        self.sessions.lock().unwrap().get(&id).cloned()
+    }
+
+    pub fn remove(&self, id: SessionId) -> Option<ProcessState> {
+        self.sessions.lock().unwrap().remove(&id)
+    }
+}
+
+/// Extracts the `SessionId` for a request from the `X-Session-Id` header,
+/// falling back to a `session_id` query parameter for plain browser
+/// requests that can't set custom headers (e.g. the static hex-view page).
+///
+/// The header is read as a raw string rather than `warp::header::optional::<SessionId>`:
+/// the typed extractor rejects the request outright when the header is
+/// present but fails to parse, which would skip the query-param fallback
+/// entirely. Parsing it ourselves lets a malformed header still fall
+/// through to a valid `session_id` query param.
+pub fn with_session_id() -> impl Filter<Extract = (SessionId,), Error = warp::Rejection> + Clone {
+    warp::header::optional::<String>("x-session-id")
+        .and(warp::query::<HashMap<String, String>>())
+        .and_then(|header: Option<String>, query: HashMap<String, String>| async move {
+            if let Some(id) = header.as_deref().and_then(|h| h.parse().ok()) {
+                return Ok(id);
+            }
+            query
+                .get("session_id")
+                .and_then(|s| s.parse().ok())
+                .ok_or_else(|| warp::reject::custom(MissingSessionId))
+        })
+}
+
+#[derive(Debug)]
+pub struct MissingSessionId;
+impl warp::reject::Reject for MissingSessionId {}
+
+#[derive(Debug)]
+pub struct SessionNotFound;
+impl warp::reject::Reject for SessionNotFound {}
+
+/// Turns `MissingSessionId`/`SessionNotFound` into a 400; every other
+/// rejection is passed through unchanged, matching `auth::handle_rejection`
+/// and `freeze::handle_rejection`.
+pub async fn handle_rejection(
+    err: warp::Rejection,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let message = if err.find::<MissingSessionId>().is_some() {
+        "missing X-Session-Id header or session_id query parameter"
+    } else if err.find::<SessionNotFound>().is_some() {
+        "no process attached for this session"
+    } else {
+        return Err(err);
+    };
+
+    Ok(warp::reply::with_status(
+        warp::reply::json(&serde_json::json!({"error": message})),
+        warp::http::StatusCode::BAD_REQUEST,
+    ))
+}
+
+/// Resolves the `X-Session-Id`/`session_id` request into the attached
+/// `ProcessState` it names, rejecting with `SessionNotFound` when no
+/// process is registered under that id. This is what every handler that
+/// operates on a specific attached process should thread in — as opposed
+/// to `api::with_state`, which just hands back the raw registry and is only
+/// appropriate for `open_process`, which doesn't have a session yet.
+pub fn with_state(
+    session_manager: SharedSessionManager,
+) -> impl Filter<Extract = (ProcessState,), Error = warp::Rejection> + Clone {
+    with_session_id().and_then(move |session_id: SessionId| {
+        let session_manager = session_manager.clone();
+        async move {
+            session_manager
+                .get(session_id)
+                .ok_or_else(|| warp::reject::custom(SessionNotFound))
+        }
+    })
+}